@@ -1,79 +1,155 @@
 use std::io::{self, Write};
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use std::time::{Duration, Instant};
+use std::sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::time::Duration;
 
 use clap::Parser;
-use rand::{prelude::*, distributions::Uniform};
+use crossbeam_channel::{after, never, select, tick};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
+mod stats;
+mod trial;
 
+use stats::Stats;
+use trial::{Birthday, Cards, MontyHall, Trial};
 
 #[derive(Debug, Clone, clap::Parser)]
-struct Args {
-    #[arg(short, long)]
-    /// number of total doors
-    doors: usize,
-
-    #[arg(short, long)]
-    /// number of doors to open by host
-    open: Option<usize>,
+struct Cli {
+    #[command(flatten)]
+    common: CommonArgs,
 
-    #[arg(short, long)]
-    /// wether to change the selected door
-    change: bool,
+    #[command(subcommand)]
+    game: Game,
+}
 
+#[derive(Debug, Clone, clap::Args)]
+struct CommonArgs {
     #[arg(short, long)]
-    /// if set automaticly terminates after the 
+    /// if set automaticly terminates after the
     /// specified number of seconds
     run_for_seconds: Option<usize>,
+
+    #[arg(long, default_value_t = 1.96)]
+    /// z-score used for the confidence interval, e.g. 1.96 for ~95%
+    /// confidence (this is a z-score, not a confidence level - passing 0.95
+    /// here would give a near-zero interval)
+    z_score: f64,
+
+    #[arg(short, long)]
+    /// if set, automaticly stops once the confidence interval half-width
+    /// drops below this value, instead of relying on `--run-for-seconds`
+    /// or a manual enter press
+    epsilon: Option<f64>,
+
+    #[arg(long, value_enum, default_value_t = Sink::Atomic)]
+    /// how per-trial results are aggregated from the worker threads
+    sink: Sink,
+
+    #[arg(long)]
+    /// master seed for the per-worker RNGs; if unset a random one is
+    /// generated and reported, so every run can still be replayed exactly
+    seed: Option<u64>,
+
+    #[arg(long)]
+    /// pin each worker-{i} thread to a core (round-robin over the available
+    /// cores) to avoid cross-core migration hurting cache locality
+    pin: bool,
+}
+
+/// Mixes the bits of `x`, splitmix64-style, to decorrelate the per-worker
+/// seeds derived from a single master seed.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+#[derive(Debug, Clone, clap::Subcommand)]
+enum Game {
+    /// the classic Monty Hall problem
+    MontyHall(MontyHall),
+    /// probability that two people in a room share a birthday
+    Birthday(Birthday),
+    /// probability of being dealt a pair or a flush from a shuffled deck
+    Cards(Cards),
 }
 
-impl Args {
-    fn open(&self) -> usize { self.open.unwrap_or(self.doors - 2) }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Sink {
+    /// push every trial result through a channel
+    Channel,
+    /// accumulate locally per worker and periodically flush into shared atomics
+    Atomic,
+}
+
+/// How many trials a worker accumulates locally before flushing into the
+/// shared atomics. Only relevant for [`Sink::Atomic`].
+const ATOMIC_FLUSH_INTERVAL: u64 = 1024;
+
+/// Per-worker write handle for reporting trial results, one per [`Sink`] variant.
+enum Aggregator {
+    Channel(crossbeam_channel::Sender<bool>),
+    Atomic {
+        wins: Arc<AtomicU64>,
+        total: Arc<AtomicU64>,
+        local_wins: u64,
+        local_total: u64,
+    },
 }
 
-#[inline]
-fn play_single(args: &Args, doors: &mut [bool], rng: &mut ThreadRng, door_dist: &Uniform<usize>) -> bool {
-    // select index of winning car
-    let car_pos = rng.sample(door_dist);
-    // select door for user
-    let pick = rng.sample(door_dist);
-
-    doors.fill(false);
-
-    if args.change {
-        let to_open = args.open();
-        let mut opened = 0;
-        let mut current = 0;
-        while opened < to_open {
-            if current != car_pos && current != pick {
-                doors[current] = true;
-                // open the door 
-                opened += 1;
+impl Aggregator {
+    fn record(&mut self, won: bool) {
+        match self {
+            Aggregator::Channel(chan) => { let _ = chan.send(won); }
+            Aggregator::Atomic { wins, total, local_wins, local_total } => {
+                *local_total += 1;
+                *local_wins += won as u64;
+                if *local_total >= ATOMIC_FLUSH_INTERVAL {
+                    wins.fetch_add(*local_wins, Ordering::Relaxed);
+                    total.fetch_add(*local_total, Ordering::Relaxed);
+                    *local_wins = 0;
+                    *local_total = 0;
+                }
             }
+        }
+    }
 
-            current = current + 1;
+    /// Flushes any trials accumulated locally but not yet visible in the
+    /// shared atomics. A no-op for [`Aggregator::Channel`].
+    fn flush(&mut self) {
+        if let Aggregator::Atomic { wins, total, local_wins, local_total } = self {
+            if *local_total > 0 {
+                wins.fetch_add(*local_wins, Ordering::Relaxed);
+                total.fetch_add(*local_total, Ordering::Relaxed);
+                *local_wins = 0;
+                *local_total = 0;
+            }
         }
-        let to_open = doors.iter().copied().enumerate().position(|(i, v)| !v && i != pick).unwrap();
-        car_pos == to_open
-    } else { car_pos == pick }
+    }
 }
 
-fn play(args: Arc<Args>, cancel: Arc<AtomicBool>, mut chan: bufchan::Sender<bool>) {
-    let mut rng = rand::thread_rng();
-    let dist = Uniform::new(0, args.doors);
-    let mut doors = vec![false; args.doors];
+fn play<T: Trial>(trial: Arc<T>, cancel: Arc<AtomicBool>, mut aggregator: Aggregator, seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut state = T::State::default();
 
     while !cancel.load(Ordering::Relaxed) {
-        chan.send(play_single(&args, &mut doors, &mut rng, &dist))
+        aggregator.record(trial.run(&mut state, &mut rng));
     }
+    aggregator.flush();
 }
 
-fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt().init();
-    let args = Arc::new(Args::parse());
+fn run_simulation<T: Trial + std::fmt::Debug + Send + Sync + 'static>(
+    trial: T,
+    common: CommonArgs,
+) -> anyhow::Result<()> {
+    let trial = Arc::new(trial);
+    let common = Arc::new(common);
     let cancel = Arc::new(AtomicBool::new(false));
 
-    let (tx, mut rx) = bufchan::unbounded();
+    let (tx, rx) = crossbeam_channel::unbounded::<bool>();
+    let shared_wins = Arc::new(AtomicU64::new(0));
+    let shared_total = Arc::new(AtomicU64::new(0));
 
     tracing::info!("getting available parrallelism on system");
     let n_threads = std::thread::available_parallelism().unwrap_or_else(|_| {
@@ -82,21 +158,39 @@ fn main() -> anyhow::Result<()> {
     });
     tracing::info!("preparing {n_threads} worker threads");
 
-    tracing::info!("running the game");
-    tracing::info!("total doors: {}", args.doors);
-    tracing::info!("opening {} door(s)", args.open());
-    tracing::info!("changing doors: {}", args.change);
+    let seed = common.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    tracing::info!(
+        "running the game (seed: {seed}{})",
+        if common.seed.is_some() { "" } else { ", randomly generated" },
+    );
+    tracing::info!("trial parameters: {trial:?}");
+
+    let core_ids = common.pin.then(core_affinity::get_core_ids).flatten()
+        .filter(|ids| !ids.is_empty());
 
     let threads = (0..n_threads.get())
         .map(|i| {
             std::thread::Builder::new()
                 .name(format!("worker-{i}"))
             .spawn({
-                let args = Arc::clone(&args);
+                let trial = Arc::clone(&trial);
                 let cancel = Arc::clone(&cancel);
-                let chan = tx.clone();
+                let aggregator = match common.sink {
+                    Sink::Channel => Aggregator::Channel(tx.clone()),
+                    Sink::Atomic => Aggregator::Atomic {
+                        wins: Arc::clone(&shared_wins),
+                        total: Arc::clone(&shared_total),
+                        local_wins: 0,
+                        local_total: 0,
+                    },
+                };
+                let worker_seed = splitmix64(seed ^ i as u64);
+                let core = core_ids.as_ref().map(|ids| ids[i % ids.len()]);
                 move || {
-                    play(args, cancel, chan);
+                    if let Some(core) = core {
+                        core_affinity::set_for_current(core);
+                    }
+                    play(trial, cancel, aggregator, worker_seed);
                     tracing::info!("worker-{i} finished");
                 }
             })
@@ -105,42 +199,115 @@ fn main() -> anyhow::Result<()> {
 
     drop(tx);
 
+    // fires once the user presses enter, letting them cut a run short regardless of mode
+    let (enter_tx, enter_rx) = crossbeam_channel::bounded::<()>(0);
+    std::thread::Builder::new().name("enter-watcher".into()).spawn(move || {
+        let _ = std::io::stdin().read_line(&mut String::new());
+        let _ = enter_tx.send(());
+    })?;
+    tracing::info!("press enter to stop counting early");
+
+    // fires once `--run-for-seconds` elapses; `never()` keeps the select arm's
+    // type uniform when no such deadline was requested
+    let timer_rx = match common.run_for_seconds {
+        Some(v) => after(Duration::from_secs(v as u64)),
+        None => never(),
+    };
+
     let collector_thread = std::thread::spawn({
+        let common = Arc::clone(&common);
+        let cancel = Arc::clone(&cancel);
+        let shared_wins = Arc::clone(&shared_wins);
+        let shared_total = Arc::clone(&shared_total);
         move || {
             println!();
-            let mut total = 0usize;
-            let mut wins = 0usize;
-            let mut start = Instant::now();
-
-            while let Some(won) = rx.recv() {
-                wins += won as usize;
-                total += 1;
-                if start.elapsed().as_secs_f32() > 1.0 {
-                    start = Instant::now();
-                    print!("\rrunning: {wins} wins ({total} games)");
-                    std::io::stdout().lock().flush().expect("could not flush");
+            let mut stats = Stats::default();
+            let ticker = tick(Duration::from_secs(1));
+
+            // the atomic sink never sends on `rx`, so selecting on it directly
+            // would busy-spin once all senders are dropped and it disconnects;
+            // `never()` keeps that arm permanently idle instead
+            let results_rx = if common.sink == Sink::Channel { rx } else { never() };
+
+            let report = |stats: &Stats| {
+                let interval = stats.wilson_interval(common.z_score);
+
+                if let (Some(epsilon), Some(interval)) = (common.epsilon, interval) {
+                    if interval.half_width < epsilon {
+                        cancel.store(true, Ordering::Relaxed);
+                    }
+                }
+
+                if let Some(interval) = interval {
+                    print!("\rrunning: {} wins ({} games), p = {interval}", stats.wins, stats.total);
+                } else {
+                    print!("\rrunning: {} wins ({} games)", stats.wins, stats.total);
                 }
+                std::io::stdout().lock().flush().expect("could not flush");
+            };
+
+            'report: loop {
+                select! {
+                    recv(results_rx) -> msg => {
+                        if let Ok(won) = msg {
+                            stats.wins += won as u64;
+                            stats.total += 1;
+                        }
+                    },
+                    recv(ticker) -> _ => {
+                        if common.sink == Sink::Atomic {
+                            stats.wins = shared_wins.load(Ordering::Relaxed);
+                            stats.total = shared_total.load(Ordering::Relaxed);
+                        }
+                        report(&stats);
+                        if cancel.load(Ordering::Relaxed) { break 'report; }
+                    },
+                    recv(enter_rx) -> _ => break 'report,
+                    recv(timer_rx) -> _ => break 'report,
+                }
+            }
+
+            cancel.store(true, Ordering::Relaxed);
+
+            // drain whatever is still buffered in the results channel so the
+            // final tally is not short-changed by the arm that just won
+            while let Ok(won) = results_rx.try_recv() {
+                stats.wins += won as u64;
+                stats.total += 1;
             }
+
             println!();
             tracing::info!("collector finshed");
-            (wins, total)
+            stats
         }
     });
 
-    if let Some(v) = args.run_for_seconds {
-        tracing::info!("running for {v} seconds");
-        std::thread::sleep(Duration::from_secs(v as _));
-    } else {
-        tracing::info!("press enter to stop counting");
-        std::io::stdin().read_line(&mut String::new())?;
-    }
-
+    let mut stats = collector_thread.join().expect("got an error joining the collector thread");
     cancel.store(true, Ordering::Relaxed);
     for t in threads { t.join().expect("got an error joining a worker thread") }
-    let (wins, total) = collector_thread.join().expect("got an error joining the collector thread");
 
-    tracing::info!("won {wins} times (out of {total} games): {:.2}%", (wins as f64 / total as f64) * 100.0);
+    // workers have now flushed their local counters; re-sample the atomics
+    // so the final totals reflect every trial, not just the last progress tick
+    if common.sink == Sink::Atomic {
+        stats.wins = shared_wins.load(Ordering::Relaxed);
+        stats.total = shared_total.load(Ordering::Relaxed);
+    }
+
+    tracing::info!(
+        "won {} times (out of {} games): {:.2}%",
+        stats.wins, stats.total, stats.proportion() * 100.0,
+    );
 
     Ok(())
 }
 
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt().init();
+    let cli = Cli::parse();
+
+    match cli.game {
+        Game::MontyHall(trial) => run_simulation(trial, cli.common),
+        Game::Birthday(trial) => run_simulation(trial, cli.common),
+        Game::Cards(trial) => run_simulation(trial, cli.common),
+    }
+}
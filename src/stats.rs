@@ -0,0 +1,45 @@
+//! Running win/trial counters and the confidence-interval math used to
+//! decide when a Monte Carlo estimate is good enough.
+
+/// A Wilson score confidence interval on a binomial proportion.
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    pub center: f64,
+    pub half_width: f64,
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.4} \u{b1} {:.4}", self.center, self.half_width)
+    }
+}
+
+/// A running `wins`/`total` counter with statistical helpers layered on top.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub wins: u64,
+    pub total: u64,
+}
+
+impl Stats {
+    pub fn proportion(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.wins as f64 / self.total as f64 }
+    }
+
+    /// Computes the Wilson score interval for the win proportion using the
+    /// given z-score (e.g. `1.96` for ~95% confidence).
+    ///
+    /// Returns `None` while there is no data yet (`total == 0`).
+    pub fn wilson_interval(&self, z: f64) -> Option<Interval> {
+        if self.total == 0 { return None; }
+
+        let n = self.total as f64;
+        let p = self.proportion();
+        let z2 = z * z;
+
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half_width = (z / (1.0 + z2 / n)) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+        Some(Interval { center, half_width })
+    }
+}
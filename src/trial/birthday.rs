@@ -0,0 +1,43 @@
+use rand::{distributions::Uniform, prelude::*};
+
+use super::Trial;
+
+/// The birthday paradox: with `people` people each assigned one of `days`
+/// equally-likely birthdays, what's the probability that two of them share
+/// one?
+#[derive(Debug, Clone, clap::Args)]
+pub struct Birthday {
+    #[arg(short, long)]
+    /// number of people in the room
+    pub people: usize,
+
+    #[arg(short, long, default_value_t = 365)]
+    /// number of equally-likely birthdays (days in a year)
+    pub days: usize,
+}
+
+#[derive(Default)]
+pub struct State {
+    seen: Vec<bool>,
+    day_dist: Option<Uniform<usize>>,
+}
+
+impl Trial for Birthday {
+    type State = State;
+
+    fn run<R: Rng>(&self, state: &mut Self::State, rng: &mut R) -> bool {
+        if state.seen.len() != self.days {
+            state.seen = vec![false; self.days];
+            state.day_dist = Some(Uniform::new(0, self.days));
+        }
+        state.seen.fill(false);
+
+        let day_dist = state.day_dist.as_ref().unwrap();
+        for _ in 0..self.people {
+            let day = rng.sample(day_dist);
+            if state.seen[day] { return true; }
+            state.seen[day] = true;
+        }
+        false
+    }
+}
@@ -0,0 +1,82 @@
+use rand::prelude::*;
+
+use super::Trial;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Value {
+    Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace,
+}
+
+const VALUES: [Value; 13] = [
+    Value::Two, Value::Three, Value::Four, Value::Five, Value::Six, Value::Seven,
+    Value::Eight, Value::Nine, Value::Ten, Value::Jack, Value::Queen, Value::King, Value::Ace,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub value: Value,
+    pub suit: Suit,
+}
+
+fn full_deck() -> Vec<Card> {
+    SUITS.iter()
+        .flat_map(|&suit| VALUES.iter().map(move |&value| Card { value, suit }))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CardsMode {
+    /// at least two cards in the hand share a value
+    Pair,
+    /// every card in the hand shares a suit
+    Flush,
+}
+
+/// Probability of being dealt a pair or a flush from a shuffled standard
+/// 52-card deck.
+#[derive(Debug, Clone, clap::Args)]
+pub struct Cards {
+    #[arg(short = 'n', long, default_value_t = 5, value_parser = clap::value_parser!(u8).range(1..=52))]
+    /// number of cards dealt per hand (1-52)
+    pub hand_size: u8,
+
+    #[arg(short, long, value_enum, default_value_t = CardsMode::Pair)]
+    /// which hand condition counts as a "win"
+    pub mode: CardsMode,
+}
+
+#[derive(Default)]
+pub struct State {
+    deck: Vec<Card>,
+}
+
+impl Trial for Cards {
+    type State = State;
+
+    fn run<R: Rng>(&self, state: &mut Self::State, rng: &mut R) -> bool {
+        if state.deck.len() != 52 {
+            state.deck = full_deck();
+        }
+        state.deck.shuffle(rng);
+        let hand = &state.deck[..self.hand_size as usize];
+
+        match self.mode {
+            CardsMode::Pair => {
+                let mut values: Vec<Value> = hand.iter().map(|c| c.value).collect();
+                values.sort();
+                values.windows(2).any(|w| w[0] == w[1])
+            }
+            CardsMode::Flush => hand.iter().all(|c| c.suit == hand[0].suit),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+//! Pluggable Monte Carlo trial games run by the worker threads.
+//!
+//! A [`Trial`] owns its fixed parameters (e.g. number of doors) and is
+//! parsed straight from the CLI as a subcommand; the mutable scratch space a
+//! single run needs lives in its associated `State` so the harness can reuse
+//! it across trials instead of allocating on every call.
+
+mod birthday;
+mod cards;
+mod monty_hall;
+
+pub use birthday::Birthday;
+pub use cards::Cards;
+pub use monty_hall::MontyHall;
+
+use rand::Rng;
+
+pub trait Trial {
+    type State: Default;
+
+    /// Runs a single trial, returning whether it counts as a "win".
+    fn run<R: Rng>(&self, state: &mut Self::State, rng: &mut R) -> bool;
+}
@@ -0,0 +1,68 @@
+use rand::{distributions::Uniform, prelude::*};
+
+use super::Trial;
+
+/// The classic Monty Hall problem: a car is behind one of `doors` doors, the
+/// host opens some number of the remaining goat doors, and the player may
+/// optionally switch their pick.
+#[derive(Debug, Clone, clap::Args)]
+pub struct MontyHall {
+    #[arg(short, long)]
+    /// number of total doors
+    pub doors: usize,
+
+    #[arg(short, long)]
+    /// number of doors to open by host
+    pub open: Option<usize>,
+
+    #[arg(short, long)]
+    /// wether to change the selected door
+    pub change: bool,
+}
+
+impl MontyHall {
+    fn open(&self) -> usize { self.open.unwrap_or(self.doors - 2) }
+}
+
+#[derive(Default)]
+pub struct State {
+    doors: Vec<bool>,
+    door_dist: Option<Uniform<usize>>,
+}
+
+impl Trial for MontyHall {
+    type State = State;
+
+    fn run<R: Rng>(&self, state: &mut Self::State, rng: &mut R) -> bool {
+        if state.doors.len() != self.doors {
+            state.doors = vec![false; self.doors];
+            state.door_dist = Some(Uniform::new(0, self.doors));
+        }
+        let doors = &mut state.doors;
+        let door_dist = state.door_dist.as_ref().unwrap();
+
+        // select index of winning car
+        let car_pos = rng.sample(door_dist);
+        // select door for user
+        let pick = rng.sample(door_dist);
+
+        doors.fill(false);
+
+        if self.change {
+            let to_open = self.open();
+            let mut opened = 0;
+            let mut current = 0;
+            while opened < to_open {
+                if current != car_pos && current != pick {
+                    doors[current] = true;
+                    // open the door
+                    opened += 1;
+                }
+
+                current = current + 1;
+            }
+            let to_open = doors.iter().copied().enumerate().position(|(i, v)| !v && i != pick).unwrap();
+            car_pos == to_open
+        } else { car_pos == pick }
+    }
+}